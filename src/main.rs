@@ -1,26 +1,82 @@
 use anyhow::{Context, Result};
+use futures::stream::{FuturesUnordered, StreamExt};
 use octocrab::{Octocrab, models, params::State};
 use std::{
     collections::{HashMap, HashSet},
     env,
 };
 
+/// How many per-PR/per-member detail requests we allow in flight at once.
+/// Keeps us from hammering the GitHub API on repos with dozens of open PRs.
+const FETCH_CONCURRENCY: usize = 8;
+
+/// Cap on how many changed files we run blame over, to bound API cost on
+/// PRs that touch a lot of files. We take the top-N by change size.
+const MAX_BLAME_FILES: usize = 20;
+
 #[derive(Debug)]
 struct Config {
-    github_token: String,
+    github_token: Option<String>,
+    app_id: Option<u64>,
+    private_key: Option<String>,
     team_members: Vec<String>,
     weight_open_prs: f64,
     weight_lines: f64,
     weight_recent: f64,
+    weight_ownership: f64,
+    weight_changes_requested_multiplier: f64,
     repo_owner: String,
     repo_name: String,
     pr_number: u64,
+    max_assigned_prs: Option<usize>,
+    max_assigned_prs_overrides: HashMap<String, usize>,
+    number_of_reviewers: usize,
+    skip_draft: bool,
+}
+
+/// Parses `INPUT_MAX_ASSIGNED_PRS` overrides of the form `alice:3,bob:5` into
+/// a per-member cap map. Entries that don't parse as `name:count` are skipped.
+fn parse_max_assigned_prs_overrides(raw: &str) -> HashMap<String, usize> {
+    let mut overrides = HashMap::new();
+
+    for entry in raw.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        if let Some((name, count)) = entry.split_once(':')
+            && let Ok(count) = count.trim().parse::<usize>()
+        {
+            overrides.insert(name.trim().to_string(), count);
+        }
+    }
+
+    overrides
 }
 
 impl Config {
     fn from_env() -> Result<Self> {
+        let github_token = env::var("INPUT_GITHUB_TOKEN").ok();
+        let app_id = env::var("INPUT_APP_ID")
+            .ok()
+            .map(|raw| raw.trim().parse::<u64>())
+            .transpose()
+            .context("Invalid INPUT_APP_ID")?;
+        let private_key = env::var("INPUT_PRIVATE_KEY").ok();
+
+        if github_token.is_none() && (app_id.is_none() || private_key.is_none()) {
+            anyhow::bail!(
+                "Must provide either INPUT_GITHUB_TOKEN, or both INPUT_APP_ID and INPUT_PRIVATE_KEY"
+            );
+        }
+
         Ok(Config {
-            github_token: env::var("INPUT_GITHUB_TOKEN").context("Missing INPUT_GITHUB_TOKEN")?,
+            github_token,
+            app_id,
+            private_key,
+            // May contain `@org/team-slug` entries, which are expanded into
+            // individual logins once we have a client to call the Teams API.
             team_members: env::var("INPUT_TEAM_MEMBERS")
                 .context("Missing INPUT_TEAM_MEMBERS")?
                 .split(',')
@@ -38,6 +94,16 @@ impl Config {
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
                 .context("Invalid weight-recent-reviews")?,
+            weight_ownership: env::var("INPUT_WEIGHT_OWNERSHIP")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid weight-ownership")?,
+            weight_changes_requested_multiplier: env::var(
+                "INPUT_WEIGHT_CHANGES_REQUESTED_MULTIPLIER",
+            )
+            .unwrap_or_else(|_| "1.5".to_string())
+            .parse()
+            .context("Invalid weight-changes-requested-multiplier")?,
             repo_owner: env::var("GITHUB_REPOSITORY_OWNER")
                 .context("Missing GITHUB_REPOSITORY_OWNER")?,
             repo_name: env::var("GITHUB_REPOSITORY")
@@ -52,22 +118,61 @@ impl Config {
                 .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
                 .and_then(|json| json["pull_request"]["number"].as_u64())
                 .context("Could not extract PR number from event")?,
+            max_assigned_prs: env::var("INPUT_MAX_ASSIGNED_PRS")
+                .ok()
+                .and_then(|raw| raw.trim().parse::<usize>().ok()),
+            max_assigned_prs_overrides: env::var("INPUT_MAX_ASSIGNED_PRS")
+                .ok()
+                .map(|raw| parse_max_assigned_prs_overrides(&raw))
+                .unwrap_or_default(),
+            number_of_reviewers: env::var("INPUT_NUMBER_OF_REVIEWERS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .context("Invalid INPUT_NUMBER_OF_REVIEWERS")?,
+            skip_draft: env::var("INPUT_SKIP_DRAFT")
+                .map(|v| v.trim().eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
         })
     }
+
+    /// Returns the work-queue cap for `member`: their per-member override if
+    /// one was given, otherwise the global `INPUT_MAX_ASSIGNED_PRS`, otherwise
+    /// no cap. This is a cap on the literal number of open PRs a member is on
+    /// the hook for (`ReviewerWorkload::raw_open_prs_count`), not on the
+    /// review-state-weighted busy-score used for ranking — so `3` always
+    /// means three PRs, regardless of `weight_changes_requested_multiplier`.
+    fn max_assigned_prs_for(&self, member: &str) -> Option<usize> {
+        self.max_assigned_prs_overrides
+            .get(member)
+            .copied()
+            .or(self.max_assigned_prs)
+    }
 }
 
+/// Workload is weighted by review state rather than a raw tally: PRs where
+/// the member's latest review is APPROVED contribute nothing (their work is
+/// effectively done), and PRs under CHANGES_REQUESTED are scaled up by
+/// `weight_changes_requested_multiplier` since they mean active
+/// back-and-forth. Hence these are `f64`, not plain counts. `raw_open_prs_count`
+/// is kept alongside as the literal, unweighted PR tally: `max_assigned_prs`
+/// and its overrides are a work-queue cap on how many PRs a member is
+/// literally on the hook for, so they're checked against that, not the
+/// weighted busy-score.
 #[derive(Debug)]
 struct ReviewerWorkload {
-    open_prs_count: usize,        // How many PRs they're reviewing
-    total_lines_in_review: usize, // Total lines across all PRs
+    open_prs_count: f64,         // Weighted count of PRs they're still on the hook for
+    raw_open_prs_count: usize,   // Literal count of those same PRs, unweighted
+    total_lines_in_review: f64,  // Weighted line total across those PRs
 }
 
 #[derive(Debug)]
 struct ReviewerScore {
     username: String,
-    open_prs_count: usize,
-    total_lines_in_review: usize,
+    open_prs_count: f64,
+    raw_open_prs_count: usize,
+    total_lines_in_review: f64,
     recent_reviews_count: usize,
+    ownership_lines: usize,
     total_score: f64,
 }
 
@@ -97,38 +202,388 @@ async fn fetch_recent_reviews(
     Ok(result.total_count.unwrap_or(0) as usize)
 }
 
+/// A single file changed by the PR, with enough info to rank files by size
+/// so we only run blame over the top `MAX_BLAME_FILES` of them, plus the
+/// unified diff `patch` text so we can work out which lines were touched.
+struct ChangedFile {
+    filename: String,
+    changes: u64,
+    patch: Option<String>,
+}
+
+async fn fetch_changed_files(
+    octocrab: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+) -> Result<Vec<ChangedFile>> {
+    let mut files = vec![];
+
+    let mut page = octocrab
+        .pulls(owner, repo)
+        .list_files(pr_number)
+        .await
+        .context(format!("Failed to fetch changed files for PR #{}", pr_number))?;
+
+    loop {
+        for file in &page {
+            files.push(ChangedFile {
+                filename: file.filename.clone(),
+                changes: file.changes,
+                patch: file.patch.clone(),
+            });
+        }
+
+        page = match octocrab
+            .get_page::<models::pulls::FileDiff>(&page.next)
+            .await
+            .context("Failed to get next page of changed files")?
+        {
+            Some(next_page) => next_page,
+            None => break,
+        }
+    }
+
+    Ok(files)
+}
+
+const BLAME_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $path: String!, $oid: GitObjectID!) {
+  repository(owner: $owner, name: $repo) {
+    object(oid: $oid) {
+      ... on Commit {
+        blame(path: $path) {
+          ranges {
+            startingLine
+            endingLine
+            commit {
+              author {
+                user {
+                  login
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(serde::Deserialize)]
+struct BlameQueryResponse {
+    repository: Option<BlameRepository>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameRepository {
+    object: Option<BlameTarget>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameTarget {
+    blame: Option<BlameInfo>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameInfo {
+    ranges: Vec<BlameRange>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameRange {
+    #[serde(rename = "startingLine")]
+    starting_line: i64,
+    #[serde(rename = "endingLine")]
+    ending_line: i64,
+    commit: BlameCommit,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameCommit {
+    author: Option<BlameAuthor>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameAuthor {
+    user: Option<BlameUser>,
+}
+
+#[derive(serde::Deserialize)]
+struct BlameUser {
+    login: String,
+}
+
+/// Parses the old-file line ranges a unified diff's hunk headers
+/// (`@@ -start,len +start,len @@`) touch, as inclusive `(start, end)` pairs.
+/// We use the `-` (old-file) side since blame is taken at `base_sha`, i.e.
+/// before the PR's changes. A hunk with an old-side length of 0 (a pure
+/// insertion) doesn't modify any existing line, so it contributes no range.
+fn parse_touched_line_ranges(patch: &str) -> Vec<(i64, i64)> {
+    let mut ranges = vec![];
+
+    for line in patch.lines() {
+        let Some(rest) = line.strip_prefix("@@ -") else {
+            continue;
+        };
+        let Some(old_range) = rest.split_whitespace().next() else {
+            continue;
+        };
+
+        let mut parts = old_range.splitn(2, ',');
+        let Some(Ok(start)) = parts.next().map(|s| s.parse::<i64>()) else {
+            continue;
+        };
+        let len: i64 = parts
+            .next()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1);
+
+        if len > 0 {
+            ranges.push((start, start + len - 1));
+        }
+    }
+
+    ranges
+}
+
+/// Fetches per-author line ownership for a single file as of `base_sha`,
+/// via GitHub's blame GraphQL API, limited to the lines `touched_ranges`
+/// says the PR actually modifies rather than the whole file.
+async fn fetch_file_blame_ownership(
+    octocrab: &Octocrab,
+    owner: &str,
+    repo: &str,
+    base_sha: &str,
+    file_path: &str,
+    touched_ranges: &[(i64, i64)],
+) -> Result<HashMap<String, usize>> {
+    let body = serde_json::json!({
+        "query": BLAME_QUERY,
+        "variables": {
+            "owner": owner,
+            "repo": repo,
+            "path": file_path,
+            "oid": base_sha,
+        }
+    });
+
+    let response: BlameQueryResponse = octocrab
+        .graphql(&body)
+        .await
+        .context(format!("Failed to fetch blame for {}", file_path))?;
+
+    let mut ownership = HashMap::new();
+
+    if let Some(blame) = response
+        .repository
+        .and_then(|r| r.object)
+        .and_then(|o| o.blame)
+    {
+        for range in blame.ranges {
+            let Some(login) = range.commit.author.and_then(|a| a.user).map(|u| u.login) else {
+                continue;
+            };
+
+            // Sum how much of this blame range overlaps with the hunks the
+            // PR touches, rather than crediting the whole range.
+            let touched_lines: i64 = touched_ranges
+                .iter()
+                .map(|&(start, end)| {
+                    (range.ending_line.min(end) - range.starting_line.max(start) + 1).max(0)
+                })
+                .sum();
+
+            if touched_lines > 0 {
+                *ownership.entry(login).or_insert(0) += touched_lines as usize;
+            }
+        }
+    }
+
+    Ok(ownership)
+}
+
+/// Thin wrapper around `fetch_file_blame_ownership` that pairs the result
+/// back up with `filename`. Pushed directly into `FuturesUnordered` (rather
+/// than wrapped in an ad hoc `async move` block at each call site), so every
+/// pushed future shares the same concrete type instead of each call site
+/// producing its own distinct anonymous type.
+async fn fetch_file_blame_ownership_keyed(
+    octocrab: &Octocrab,
+    owner: &str,
+    repo: &str,
+    base_sha: &str,
+    filename: String,
+    touched_ranges: Vec<(i64, i64)>,
+) -> (String, Result<HashMap<String, usize>>) {
+    let ownership =
+        fetch_file_blame_ownership(octocrab, owner, repo, base_sha, &filename, &touched_ranges)
+            .await;
+    (filename, ownership)
+}
+
+/// Tallies, per team member, how many of the PR's touched lines they last
+/// authored (per `git blame`), so reviewers who already own the code can be
+/// favored. Blame is fetched once per file and cached for the run; only the
+/// top `MAX_BLAME_FILES` changed files (by change size) are inspected to
+/// bound API cost. Returns an empty map (and does no API calls) when
+/// `weight_ownership` is zero, since it wouldn't affect the score anyway.
+async fn calculate_code_ownership(
+    octocrab: &Octocrab,
+    config: &Config,
+    pr_number: u64,
+) -> Result<HashMap<String, usize>> {
+    let mut ownership_by_member: HashMap<String, usize> = HashMap::new();
+
+    if config.weight_ownership == 0.0 {
+        return Ok(ownership_by_member);
+    }
+
+    println!("🔍 Analyzing code ownership for changed files...");
+
+    let pr = octocrab
+        .pulls(&config.repo_owner, &config.repo_name)
+        .get(pr_number)
+        .await
+        .context("Failed to fetch PR for ownership analysis")?;
+
+    let base_sha = pr.base.sha;
+
+    let mut files =
+        fetch_changed_files(octocrab, &config.repo_owner, &config.repo_name, pr_number).await?;
+    files.sort_by(|a, b| b.changes.cmp(&a.changes));
+    files.truncate(MAX_BLAME_FILES);
+
+    println!(
+        "  Inspecting blame for {} changed file(s) (capped at {})",
+        files.len(),
+        MAX_BLAME_FILES
+    );
+
+    // Blame per file, cached in `blame_by_file` for the rest of the run, is
+    // fetched concurrently and bounded like the per-PR and per-member fetches.
+    let mut blame_by_file: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let mut in_flight = FuturesUnordered::new();
+    let mut pending = files.into_iter();
+
+    for file in pending.by_ref().take(FETCH_CONCURRENCY) {
+        let touched_ranges = file
+            .patch
+            .as_deref()
+            .map(parse_touched_line_ranges)
+            .unwrap_or_default();
+        in_flight.push(fetch_file_blame_ownership_keyed(
+            octocrab,
+            &config.repo_owner,
+            &config.repo_name,
+            &base_sha,
+            file.filename,
+            touched_ranges,
+        ));
+    }
+
+    while let Some((filename, result)) = in_flight.next().await {
+        if let Some(next_file) = pending.next() {
+            let touched_ranges = next_file
+                .patch
+                .as_deref()
+                .map(parse_touched_line_ranges)
+                .unwrap_or_default();
+            in_flight.push(fetch_file_blame_ownership_keyed(
+                octocrab,
+                &config.repo_owner,
+                &config.repo_name,
+                &base_sha,
+                next_file.filename,
+                touched_ranges,
+            ));
+        }
+
+        match result {
+            Ok(file_ownership) => {
+                blame_by_file.insert(filename, file_ownership);
+            }
+            Err(err) => println!("  ⚠️  Skipping blame for {}: {:#}", filename, err),
+        }
+    }
+
+    for file_ownership in blame_by_file.values() {
+        for (login, lines) in file_ownership {
+            if config.team_members.contains(login) {
+                *ownership_by_member.entry(login.clone()).or_insert(0) += lines;
+            }
+        }
+    }
+
+    Ok(ownership_by_member)
+}
+
 async fn calculate_scores(
     octocrab: &Octocrab,
     config: &Config,
     workloads: HashMap<String, ReviewerWorkload>,
+    ownership: &HashMap<String, usize>,
     pr_author: &str,
+    remaining_needed: usize,
 ) -> Result<Vec<ReviewerScore>> {
     println!("🧮 Calculating scores for each reviewer...");
 
-    let mut scores = vec![];
+    let eligible_members: Vec<&String> = config
+        .team_members
+        .iter()
+        .filter(|member| member.as_str() != pr_author)
+        .collect();
 
-    for member in &config.team_members {
-        // Skip the PR author
-        if member == pr_author {
-            continue;
+    // Dispatch the recent-reviews search for every eligible member up front,
+    // bounded so we don't fire off dozens of search requests at once. Every
+    // pushed future comes from the same closure, so they share one type.
+    let fetch_keyed = move |member: String| async move {
+        let count = fetch_recent_reviews(octocrab, &config.repo_owner, &config.repo_name, &member)
+            .await
+            .unwrap_or(0);
+        (member, count)
+    };
+    let mut in_flight = FuturesUnordered::new();
+    let mut pending = eligible_members.iter();
+
+    for member in pending.by_ref().take(FETCH_CONCURRENCY) {
+        in_flight.push(fetch_keyed((*member).clone()));
+    }
+
+    let mut recent_reviews_by_member = HashMap::new();
+    while let Some((member, count)) = in_flight.next().await {
+        if let Some(next_member) = pending.next() {
+            in_flight.push(fetch_keyed((*next_member).clone()));
         }
+        recent_reviews_by_member.insert(member, count);
+    }
 
+    let mut scores = vec![];
+    let mut over_capacity = vec![];
+
+    for member in eligible_members {
         let load = workloads
             .get(member)
             .expect("Member should be in the workloads");
 
-        let recent_reviews_count =
-            fetch_recent_reviews(octocrab, &config.repo_owner, &config.repo_name, member)
-                .await
-                .unwrap_or(0);
+        let recent_reviews_count = recent_reviews_by_member
+            .get(member)
+            .copied()
+            .unwrap_or(0);
 
-        // Calculate score using the weights
-        let score = (load.open_prs_count as f64 * config.weight_open_prs)
-            + ((load.total_lines_in_review as f64 / 100.0) * config.weight_lines)
-            + (recent_reviews_count as f64 * config.weight_recent);
+        let ownership_lines = ownership.get(member).copied().unwrap_or(0);
+
+        // Calculate score using the weights. Ownership *subtracts* from the
+        // busy-score: the more of the touched code a reviewer already owns,
+        // the more likely they are to be picked.
+        let score = (load.open_prs_count * config.weight_open_prs)
+            + ((load.total_lines_in_review / 100.0) * config.weight_lines)
+            + (recent_reviews_count as f64 * config.weight_recent)
+            - (ownership_lines as f64 * config.weight_ownership);
 
         println!(
-            "  @{}: {:.2} points (Open: {} × {}, Lines: {} ÷ 100 × {}, Recent: {} × {})",
+            "  @{}: {:.2} points (Open: {:.1} × {}, Lines: {:.1} ÷ 100 × {}, Recent: {} × {}, Ownership: {} × {})",
             member,
             score,
             load.open_prs_count,
@@ -136,16 +591,39 @@ async fn calculate_scores(
             load.total_lines_in_review,
             config.weight_lines,
             recent_reviews_count,
-            config.weight_recent
+            config.weight_recent,
+            ownership_lines,
+            config.weight_ownership
         );
 
-        scores.push(ReviewerScore {
+        let reviewer_score = ReviewerScore {
             username: member.clone(),
             open_prs_count: load.open_prs_count,
+            raw_open_prs_count: load.raw_open_prs_count,
             total_lines_in_review: load.total_lines_in_review,
             recent_reviews_count,
+            ownership_lines,
             total_score: score,
-        });
+        };
+
+        // Exclude anyone already at (or over) their work-queue cap, mirroring
+        // the triagebot work-queue model. Keep them around as a fallback in
+        // case everyone is at capacity. The cap is checked against the
+        // literal PR count, not the review-state-weighted busy-score, so
+        // `INPUT_MAX_ASSIGNED_PRS=3` always means three actual PRs.
+        match config.max_assigned_prs_for(member) {
+            Some(cap) if reviewer_score.raw_open_prs_count >= cap => {
+                println!(
+                    "  ⚠️  @{} is at their work-queue cap ({}/{}), excluding from ranking",
+                    member, reviewer_score.raw_open_prs_count, cap
+                );
+                over_capacity.push((
+                    reviewer_score.raw_open_prs_count as i64 - cap as i64,
+                    reviewer_score,
+                ));
+            }
+            _ => scores.push(reviewer_score),
+        }
     }
 
     // Sort by score (lowest first)
@@ -155,14 +633,135 @@ async fn calculate_scores(
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
+    // If we don't yet have enough under-cap candidates to fill
+    // `remaining_needed`, fall back to the least-over-capacity members,
+    // mirroring the triagebot work-queue model's overflow behavior.
+    if scores.len() < remaining_needed && !over_capacity.is_empty() {
+        let still_needed = remaining_needed - scores.len();
+        println!(
+            "⚠️  Only {} of {} needed reviewer(s) are under their work-queue cap; falling back to the {} least-over-capacity member(s)",
+            scores.len(),
+            remaining_needed,
+            still_needed.min(over_capacity.len())
+        );
+        over_capacity.sort_by_key(|(over, _)| *over);
+        scores.extend(
+            over_capacity
+                .into_iter()
+                .take(still_needed)
+                .map(|(_, reviewer_score)| reviewer_score),
+        );
+    }
+
+    if scores.len() < remaining_needed {
+        println!(
+            "⚠️  Only found {} eligible reviewer(s) for the {} requested",
+            scores.len(),
+            remaining_needed
+        );
+    }
+
     Ok(scores)
 }
 
+/// Per-PR detail needed to fold a single open PR into the workload tally:
+/// its line count and, per team member still on the hook for it, how much
+/// of a unit of work they represent (see `fetch_pr_detail`).
+struct PrDetail {
+    pr_number: u64,
+    additions: u64,
+    deletions: u64,
+    reviewer_weights: HashMap<String, f64>,
+}
+
+/// Fetches a single PR's detail and reviews, and reduces them down to a
+/// per-reviewer workload weight: 1.0 for a requested-but-not-yet-reviewed
+/// member, `changes_requested_multiplier` for one whose latest *decision*
+/// review is CHANGES_REQUESTED (active back-and-forth), and 0.0 once their
+/// latest decision review is APPROVED (their work here is done).
+/// Non-decision reviews (e.g. a COMMENTED follow-up after approving) don't
+/// change an already-known weight. Errors fetching the PR itself are
+/// propagated; errors listing reviews are tolerated (some PRs might not have
+/// reviews) just like before.
+async fn fetch_pr_detail(
+    octocrab: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    team_members: &HashSet<String>,
+    changes_requested_multiplier: f64,
+) -> Result<PrDetail> {
+    let pr = octocrab
+        .pulls(owner, repo)
+        .get(pr_number)
+        .await
+        .context(format!("Failed to fetch details for PR #{}", pr_number))?;
+
+    let additions = pr.additions.unwrap_or_default();
+    let deletions = pr.deletions.unwrap_or_default();
+
+    // Track the current workload weight per reviewer for this PR.
+    let mut reviewer_weights: HashMap<String, f64> = HashMap::new();
+
+    // Requested reviewers who haven't reviewed yet count as a full unit of
+    // pending work.
+    if let Some(requested_reviewers) = pr.requested_reviewers {
+        for reviewer in requested_reviewers {
+            if team_members.contains(&reviewer.login) {
+                reviewer_weights.insert(reviewer.login.clone(), 1.0);
+            }
+        }
+    }
+
+    // Reviews layer on top, keyed by each reviewer's latest *decision*
+    // review: an approval zeroes out their weight, a change request scales
+    // it up. Non-decision reviews (COMMENTED, DISMISSED, ...) don't carry a
+    // verdict, so they only fill in a default weight if we haven't seen a
+    // decision from that reviewer yet — otherwise a routine follow-up
+    // comment after an approval would wrongly reset them back to "pending".
+    let reviews = octocrab
+        .pulls(owner, repo)
+        .list_reviews(pr_number)
+        .per_page(100)
+        .send()
+        .await
+        .ok(); // Ignore errors, some PRs might not have reviews
+
+    if let Some(reviews) = reviews {
+        for review in reviews {
+            if let Some(reviewer) = review.user
+                && team_members.contains(&reviewer.login)
+            {
+                match review.state {
+                    Some(models::pulls::ReviewState::Approved) => {
+                        reviewer_weights.insert(reviewer.login.clone(), 0.0);
+                    }
+                    Some(models::pulls::ReviewState::ChangesRequested) => {
+                        reviewer_weights
+                            .insert(reviewer.login.clone(), changes_requested_multiplier);
+                    }
+                    _ => {
+                        reviewer_weights.entry(reviewer.login.clone()).or_insert(1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(PrDetail {
+        pr_number,
+        additions,
+        deletions,
+        reviewer_weights,
+    })
+}
+
 async fn fetch_open_prs_workload(
     octocrab: &Octocrab,
     owner: &str,
     repo: &str,
     team_members: &[String],
+    changes_requested_multiplier: f64,
 ) -> Result<HashMap<String, ReviewerWorkload>> {
     let mut workload: HashMap<String, ReviewerWorkload> = HashMap::new();
 
@@ -171,12 +770,15 @@ async fn fetch_open_prs_workload(
         workload.insert(
             member.clone(),
             ReviewerWorkload {
-                open_prs_count: 0,
-                total_lines_in_review: 0,
+                open_prs_count: 0.0,
+                raw_open_prs_count: 0,
+                total_lines_in_review: 0.0,
             },
         );
     }
 
+    let team_members_set: HashSet<String> = team_members.iter().cloned().collect();
+
     let mut pr_numbers = vec![];
 
     let mut total_prs = 0;
@@ -207,73 +809,93 @@ async fn fetch_open_prs_workload(
 
     println!("  Found {} open PRs, fetching details...", pr_numbers.len());
 
-    for pr_number in pr_numbers {
-        let pr = octocrab
-            .pulls(owner, repo)
-            .get(pr_number)
-            .await
-            .context(format!("Failed to fetch details for PR #{}", pr_number))?;
+    // Fetch each PR's detail+reviews concurrently, bounded so we don't open
+    // dozens of connections to the API at once. Partial failures on
+    // individual PRs are logged and skipped rather than aborting the run.
+    let mut in_flight = FuturesUnordered::new();
+    let mut pending = pr_numbers.into_iter();
 
-        let additions = pr.additions.unwrap_or_default();
-        let deletions = pr.deletions.unwrap_or_default();
-        let lines = additions + deletions;
+    for pr_number in pending.by_ref().take(FETCH_CONCURRENCY) {
+        in_flight.push(fetch_pr_detail(
+            octocrab,
+            owner,
+            repo,
+            pr_number,
+            &team_members_set,
+            changes_requested_multiplier,
+        ));
+    }
 
-        // Track which reviewers we've counted for this PR (to avoid double-counting)
-        let mut reviewers_for_this_pr = HashSet::new();
+    let mut analyzed_prs = 0;
+    while let Some(result) = in_flight.next().await {
+        if let Some(next_pr_number) = pending.next() {
+            in_flight.push(fetch_pr_detail(
+                octocrab,
+                owner,
+                repo,
+                next_pr_number,
+                &team_members_set,
+                changes_requested_multiplier,
+            ));
+        }
 
-        // Check requested reviewers (people who haven't reviewed yet)
-        if let Some(requested_reviewers) = pr.requested_reviewers {
-            for reviewer in requested_reviewers {
-                if workload.contains_key(&reviewer.login) {
-                    reviewers_for_this_pr.insert(reviewer.login.clone());
-                }
+        let detail = match result {
+            Ok(detail) => detail,
+            Err(err) => {
+                println!("  ⚠️  Skipping PR: {:#}", err);
+                continue;
             }
-        }
+        };
 
-        // Also check people who have already submitted reviews
-        // (they might still be actively reviewing/assigned)
-        let reviews = octocrab
-            .pulls(owner, repo)
-            .list_reviews(pr_number)
-            .per_page(100)
-            .send()
-            .await
-            .ok(); // Ignore errors, some PRs might not have reviews
-
-        if let Some(reviews) = reviews {
-            for review in reviews {
-                if let Some(reviewer) = review.user
-                    && workload.contains_key(&reviewer.login)
-                {
-                    reviewers_for_this_pr.insert(reviewer.login.clone());
-                }
+        let lines = (detail.additions + detail.deletions) as f64;
+        analyzed_prs += 1;
+
+        for (reviewer, weight) in detail.reviewer_weights {
+            // A weight of zero means their latest review was an approval;
+            // they're off the hook for this PR, so skip it entirely rather
+            // than counting it as zero outstanding work.
+            if weight == 0.0 {
+                continue;
             }
-        }
 
-        for reviewer in reviewers_for_this_pr {
             if let Some(workload_entry) = workload.get_mut(&reviewer) {
-                workload_entry.open_prs_count += 1;
-                workload_entry.total_lines_in_review += lines as usize;
+                workload_entry.open_prs_count += weight;
+                workload_entry.raw_open_prs_count += 1;
+                workload_entry.total_lines_in_review += lines * weight;
 
                 println!(
-                    "  PR #{}: @{} reviewing ({} additions, {} deletions)",
-                    pr_number, reviewer, additions, deletions
+                    "  PR #{}: @{} reviewing ({} additions, {} deletions, weight {:.1})",
+                    detail.pr_number, reviewer, detail.additions, detail.deletions, weight
                 );
             }
         }
     }
 
-    println!("\n✅ Analyzed {} open PRs", total_prs);
+    println!(
+        "\n✅ Analyzed {} of {} open PRs",
+        analyzed_prs, total_prs
+    );
 
     Ok(workload)
 }
 
+/// Everything we need from the current PR to decide whether it still needs
+/// reviewers assigned: who opened it, whether it's a draft, and which team
+/// members already have it covered (requested, or with a non-dismissed
+/// review already submitted).
+struct CurrentPr {
+    author: String,
+    draft: bool,
+    covered_reviewers: HashSet<String>,
+}
+
 async fn fetch_current_pr(
     octocrab: &Octocrab,
     owner: &str,
     repo: &str,
     pr_number: u64,
-) -> Result<String> {
+    team_members: &HashSet<String>,
+) -> Result<CurrentPr> {
     println!("🔍 Fetching PR #{}...", pr_number);
 
     let pr = octocrab
@@ -283,33 +905,178 @@ async fn fetch_current_pr(
         .context("Failed to fetch PR from GitHub API")?;
 
     let author = pr.user.map(|u| u.login).context("PR has no author")?;
+    let draft = pr.draft.unwrap_or(false);
 
     println!("  Author: @{}", author);
     println!("  Title: {}", pr.title.as_deref().unwrap_or("(no title)"));
     println!("  State: {:?}", pr.state);
+    println!("  Draft: {}", draft);
+
+    let mut covered_reviewers = HashSet::new();
+
+    if let Some(requested_reviewers) = &pr.requested_reviewers {
+        for reviewer in requested_reviewers {
+            if team_members.contains(&reviewer.login) {
+                covered_reviewers.insert(reviewer.login.clone());
+            }
+        }
+    }
+
+    let reviews = octocrab
+        .pulls(owner, repo)
+        .list_reviews(pr_number)
+        .per_page(100)
+        .send()
+        .await
+        .ok(); // Ignore errors, some PRs might not have reviews
+
+    if let Some(reviews) = reviews {
+        for review in reviews {
+            let is_dismissed = matches!(review.state, Some(models::pulls::ReviewState::Dismissed));
+
+            if let Some(reviewer) = review.user
+                && team_members.contains(&reviewer.login)
+                && !is_dismissed
+            {
+                covered_reviewers.insert(reviewer.login);
+            }
+        }
+    }
+
+    Ok(CurrentPr {
+        author,
+        draft,
+        covered_reviewers,
+    })
+}
+
+/// Builds the Octocrab client from either a personal access token or a
+/// GitHub App installation. Per the wow-actions/auto-assign workflow, the
+/// default Actions token can't list team members or request teams as
+/// reviewers, so orgs that need that can configure an App instead.
+async fn build_octocrab(config: &Config) -> Result<Octocrab> {
+    if let Some(token) = &config.github_token {
+        return Octocrab::builder()
+            .personal_token(token.clone())
+            .build()
+            .context("Failed to create GitHub API client");
+    }
+
+    let app_id = config
+        .app_id
+        .context("Missing INPUT_APP_ID for App auth")?;
+    let private_key = config
+        .private_key
+        .as_deref()
+        .context("Missing INPUT_PRIVATE_KEY for App auth")?;
+
+    let key = jsonwebtoken::EncodingKey::from_rsa_pem(private_key.as_bytes())
+        .context("Invalid INPUT_PRIVATE_KEY (expected an RSA PEM key)")?;
+
+    let app_client = Octocrab::builder()
+        .app(models::AppId(app_id), key)
+        .build()
+        .context("Failed to create GitHub App client")?;
+
+    let installation = app_client
+        .apps()
+        .installations()
+        .send()
+        .await
+        .context("Failed to list App installations")?
+        .items
+        .into_iter()
+        .find(|installation| installation.account.login == config.repo_owner)
+        .context("No App installation found for this repository's owner")?;
+
+    let (installation_client, _token) = app_client
+        .installation_and_token(installation.id)
+        .await
+        .context("Failed to get an installation token")?;
+
+    Ok(installation_client)
+}
+
+/// Expands any `@org/team-slug` entries in `raw_members` into individual
+/// logins via the Teams API, leaving plain usernames untouched. This lets an
+/// org hand us a team slug instead of maintaining an explicit member list by
+/// hand.
+async fn expand_team_members(octocrab: &Octocrab, raw_members: &[String]) -> Result<Vec<String>> {
+    let mut expanded = vec![];
+
+    for member in raw_members {
+        match member.strip_prefix('@').and_then(|rest| rest.split_once('/')) {
+            Some((org, slug)) => {
+                println!("  Expanding team @{}/{}...", org, slug);
+                expanded.extend(fetch_team_members(octocrab, org, slug).await?);
+            }
+            // A bare `@login` (no `/team-slug`) is just a plain username
+            // written with a leading `@`; strip it so it matches the
+            // `@`-less logins GitHub returns elsewhere (e.g. `reviewer.login`).
+            None => expanded.push(member.strip_prefix('@').unwrap_or(member).to_string()),
+        }
+    }
+
+    expanded.sort();
+    expanded.dedup();
+
+    Ok(expanded)
+}
+
+async fn fetch_team_members(octocrab: &Octocrab, org: &str, slug: &str) -> Result<Vec<String>> {
+    let mut logins = vec![];
+
+    let mut page = octocrab
+        .teams(org)
+        .members(slug)
+        .per_page(100)
+        .send()
+        .await
+        .context(format!("Failed to list members of team @{}/{}", org, slug))?;
+
+    loop {
+        for member in &page {
+            logins.push(member.login.clone());
+        }
+
+        page = match octocrab
+            .get_page::<models::Author>(&page.next)
+            .await
+            .context("Failed to get next page of team members")?
+        {
+            Some(next_page) => next_page,
+            None => break,
+        }
+    }
 
-    Ok(author)
+    Ok(logins)
 }
 
-async fn assign_reviewer(
+async fn assign_reviewers(
     octocrab: &Octocrab,
     owner: &str,
     repo: &str,
     pr_number: u64,
-    reviewer: &str,
+    reviewers: &[String],
 ) -> Result<()> {
-    println!("🔄 Assigning @{} to PR #{}...", reviewer, pr_number);
+    let names = reviewers
+        .iter()
+        .map(|r| format!("@{}", r))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    println!("🔄 Assigning {} to PR #{}...", names, pr_number);
 
     octocrab
         .pulls(owner, repo)
-        .request_reviews(pr_number, [reviewer.to_string()], [])
+        .request_reviews(pr_number, reviewers.to_vec(), [])
         .await
         .context(format!(
-            "Failed to assign @{} as reviewer to PR #{}",
-            reviewer, pr_number
+            "Failed to assign {} as reviewer(s) to PR #{}",
+            names, pr_number
         ))?;
 
-    println!("✅ Successfully assigned @{} as reviewer!", reviewer);
+    println!("✅ Successfully assigned {} as reviewer(s)!", names);
 
     Ok(())
 }
@@ -318,48 +1085,95 @@ async fn assign_reviewer(
 async fn main() -> Result<()> {
     println!("🔍 Parsing configuration from environment...\n");
 
-    let config = Config::from_env()?;
+    let mut config = Config::from_env()?;
 
     println!("✅ Configuration loaded successfully!");
     println!("\n📋 Config Details:");
     println!("  Repository: {}/{}", config.repo_owner, config.repo_name);
     println!("  PR Number: {}", config.pr_number);
     println!("  Team Members: {:?}", config.team_members);
+    println!("  Number of reviewers: {}", config.number_of_reviewers);
+    println!("  Skip draft PRs: {}", config.skip_draft);
     println!("\n⚖️  Weights:");
     println!("  Open PRs: {}", config.weight_open_prs);
     println!("  Lines per 100: {}", config.weight_lines);
     println!("  Recent reviews: {}", config.weight_recent);
+    println!("  Ownership: {}", config.weight_ownership);
+    println!(
+        "  Changes-requested multiplier: {}",
+        config.weight_changes_requested_multiplier
+    );
+    if config.max_assigned_prs.is_some() || !config.max_assigned_prs_overrides.is_empty() {
+        println!(
+            "  Max assigned PRs: {:?} (overrides: {:?})",
+            config.max_assigned_prs, config.max_assigned_prs_overrides
+        );
+    }
 
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("🔌 Connecting to GitHub API...\n");
 
-    let octocrab = Octocrab::builder()
-        .personal_token(config.github_token.clone())
-        .build()
-        .context("Failed to create GitHub API client")?;
+    let octocrab = build_octocrab(&config).await?;
 
     println!("✅ Connected to GitHub API");
 
+    config.team_members = expand_team_members(&octocrab, &config.team_members).await?;
+    println!("  Team Members (expanded): {:?}", config.team_members);
+
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    let pr_author = fetch_current_pr(
+    let team_members_set: HashSet<String> = config.team_members.iter().cloned().collect();
+    let current_pr = fetch_current_pr(
         &octocrab,
         &config.repo_owner,
         &config.repo_name,
         config.pr_number,
+        &team_members_set,
     )
     .await?;
 
+    if config.skip_draft && current_pr.draft {
+        println!(
+            "\n⏭️  PR #{} is a draft and INPUT_SKIP_DRAFT is set; skipping assignment",
+            config.pr_number
+        );
+        return Ok(());
+    }
+
+    let already_covered = current_pr.covered_reviewers.len();
+    if already_covered >= config.number_of_reviewers {
+        println!(
+            "\n✅ PR #{} already has {} reviewer(s) (requested or reviewed), which meets the desired {}; skipping assignment",
+            config.pr_number, already_covered, config.number_of_reviewers
+        );
+        return Ok(());
+    }
+
+    let remaining_needed = config.number_of_reviewers - already_covered;
+
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     let workload = fetch_open_prs_workload(
         &octocrab,
         &config.repo_owner,
         &config.repo_name,
         &config.team_members,
+        config.weight_changes_requested_multiplier,
     )
     .await?;
 
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    let scores = calculate_scores(&octocrab, &config, workload, &pr_author).await?;
+    let ownership =
+        calculate_code_ownership(&octocrab, &config, config.pr_number).await?;
+
+    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let scores = calculate_scores(
+        &octocrab,
+        &config,
+        workload,
+        &ownership,
+        &current_pr.author,
+        remaining_needed,
+    )
+    .await?;
 
     println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("🏆 Final Rankings (lowest score = least busy):\n");
@@ -380,28 +1194,58 @@ async fn main() -> Result<()> {
             score.total_score
         );
         println!(
-            "       {} open PRs, {} lines, {} recent reviews",
-            score.open_prs_count, score.total_lines_in_review, score.recent_reviews_count
+            "       {} open PRs ({:.1} weighted), {:.1} lines, {} recent reviews, {} owned lines",
+            score.raw_open_prs_count,
+            score.open_prs_count,
+            score.total_lines_in_review,
+            score.recent_reviews_count,
+            score.ownership_lines
         );
     }
 
-    // Assign the reviewer with the lowest score
-    if let Some(winner) = scores.first() {
+    // Assign the top-ranked reviewers needed to reach the desired count
+    // (under-cap members by lowest score, then any over-capacity fallbacks)
+    let winners: Vec<String> = scores
+        .iter()
+        .take(remaining_needed)
+        .map(|score| score.username.clone())
+        .collect();
+
+    if !winners.is_empty() {
         println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("✨ Best choice: @{}", winner.username);
+        println!(
+            "✨ Best choice(s): {}",
+            winners
+                .iter()
+                .map(|u| format!("@{}", u))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if winners.len() < remaining_needed {
+            println!(
+                "⚠️  Wanted {} reviewer(s) but only {} could be assigned",
+                remaining_needed,
+                winners.len()
+            );
+        }
 
-        assign_reviewer(
+        assign_reviewers(
             &octocrab,
             &config.repo_owner,
             &config.repo_name,
             config.pr_number,
-            &winner.username,
+            &winners,
         )
         .await?;
 
         println!(
-            "\n🎉 Done! PR #{} has been assigned to @{}",
-            config.pr_number, winner.username
+            "\n🎉 Done! PR #{} has been assigned to {}",
+            config.pr_number,
+            winners
+                .iter()
+                .map(|u| format!("@{}", u))
+                .collect::<Vec<_>>()
+                .join(", ")
         );
     } else {
         println!(